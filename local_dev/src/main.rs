@@ -1,46 +1,38 @@
+mod migrations;
+mod migrator;
+
 use dynomite::{retry::Policy, Retries};
+use migrations::{CreateBooksTable, CreateSessionsTable};
+use migrator::{rollback_migrations, run_migrations, DynamoMigrationLedger, Error};
 use rusoto_core::Region;
-use rusoto_dynamodb::{
-    AttributeDefinition, CreateTableInput, DynamoDb, DynamoDbClient, KeySchemaElement,
-    ProvisionedThroughput,
-};
-use tokio::time::Error;
-
-pub async fn bootstrap<D>(client: &D, table_name: String)
-where
-    D: DynamoDb,
-{
-    let _ = client
-        .create_table(CreateTableInput {
-            table_name,
-            key_schema: vec![KeySchemaElement {
-                attribute_name: "id".into(),
-                key_type: "HASH".into(),
-            }],
-            attribute_definitions: vec![AttributeDefinition {
-                attribute_name: "id".into(),
-                attribute_type: "S".into(),
-            }],
-            provisioned_throughput: Some(ProvisionedThroughput {
-                read_capacity_units: 1,
-                write_capacity_units: 1,
-            }),
-            ..CreateTableInput::default()
-        })
-        .await;
-}
+use rusoto_dynamodb::DynamoDbClient;
 
 #[tokio::main]
 pub async fn main() -> Result<(), Error> {
-    println!("Importing tables");
     let local_client = DynamoDbClient::new(Region::Custom {
         name: "us-east-1".into(),
         endpoint: "http://localhost:8000".into(),
     })
     .with_retries(Policy::default());
 
-    bootstrap(&local_client, "books".into()).await;
-    println!("Finished: Importing tables");
+    let create_books_table = CreateBooksTable;
+    let create_sessions_table = CreateSessionsTable;
+    let migrations: Vec<&dyn migrator::Migration> =
+        vec![&create_books_table, &create_sessions_table];
+
+    let ledger = DynamoMigrationLedger::new(&local_client);
+
+    // `cargo run -- down` reverts instead of applying, for undoing a local
+    // migration run without wiping the dynamodb-local container.
+    if std::env::args().nth(1).as_deref() == Some("down") {
+        println!("Rolling back migrations");
+        rollback_migrations(&ledger, &local_client, &migrations).await?;
+        println!("Finished: Rolling back migrations");
+    } else {
+        println!("Running migrations");
+        run_migrations(&ledger, &local_client, &migrations).await?;
+        println!("Finished: Running migrations");
+    }
 
     Ok(())
 }
@@ -0,0 +1,101 @@
+use crate::migrator::{Error, Migration};
+use async_trait::async_trait;
+use dynomite::dynamodb::{
+    AttributeDefinition, CreateTableInput, DeleteTableInput, DynamoDb, KeySchemaElement,
+    ProvisionedThroughput, TimeToLiveSpecification, UpdateTimeToLiveInput,
+};
+
+pub struct CreateBooksTable;
+
+#[async_trait]
+impl Migration for CreateBooksTable {
+    fn version(&self) -> &str {
+        "0001_create_books_table"
+    }
+
+    async fn up(&self, client: &(dyn DynamoDb + Send + Sync)) -> Result<(), Error> {
+        client
+            .create_table(CreateTableInput {
+                table_name: "books".into(),
+                key_schema: vec![KeySchemaElement {
+                    attribute_name: "id".into(),
+                    key_type: "HASH".into(),
+                }],
+                attribute_definitions: vec![AttributeDefinition {
+                    attribute_name: "id".into(),
+                    attribute_type: "S".into(),
+                }],
+                provisioned_throughput: Some(ProvisionedThroughput {
+                    read_capacity_units: 1,
+                    write_capacity_units: 1,
+                }),
+                ..CreateTableInput::default()
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    async fn down(&self, client: &(dyn DynamoDb + Send + Sync)) -> Result<(), Error> {
+        client
+            .delete_table(DeleteTableInput {
+                table_name: "books".into(),
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+pub struct CreateSessionsTable;
+
+#[async_trait]
+impl Migration for CreateSessionsTable {
+    fn version(&self) -> &str {
+        "0002_create_sessions_table"
+    }
+
+    async fn up(&self, client: &(dyn DynamoDb + Send + Sync)) -> Result<(), Error> {
+        client
+            .create_table(CreateTableInput {
+                table_name: "sessions".into(),
+                key_schema: vec![KeySchemaElement {
+                    attribute_name: "token".into(),
+                    key_type: "HASH".into(),
+                }],
+                attribute_definitions: vec![AttributeDefinition {
+                    attribute_name: "token".into(),
+                    attribute_type: "S".into(),
+                }],
+                provisioned_throughput: Some(ProvisionedThroughput {
+                    read_capacity_units: 1,
+                    write_capacity_units: 1,
+                }),
+                ..CreateTableInput::default()
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+
+        client
+            .update_time_to_live(UpdateTimeToLiveInput {
+                table_name: "sessions".into(),
+                time_to_live_specification: TimeToLiveSpecification {
+                    enabled: true,
+                    attribute_name: "expiresAt".into(),
+                },
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    async fn down(&self, client: &(dyn DynamoDb + Send + Sync)) -> Result<(), Error> {
+        client
+            .delete_table(DeleteTableInput {
+                table_name: "sessions".into(),
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
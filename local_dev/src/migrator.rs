@@ -0,0 +1,257 @@
+use async_trait::async_trait;
+use dynomite::dynamodb::{
+    AttributeDefinition, AttributeValue, CreateTableInput, DeleteItemInput, DynamoDb,
+    KeySchemaElement, ProvisionedThroughput, PutItemInput, ScanInput,
+};
+use std::collections::{HashMap, HashSet};
+
+pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+const MIGRATIONS_TABLE: &str = "_migrations";
+
+/// A single step in the schema's evolution.
+///
+/// `version` must be unique and stable once shipped; `run_migrations` uses it
+/// to decide whether the migration has already been applied.
+#[async_trait]
+pub trait Migration: Sync {
+    fn version(&self) -> &str;
+    async fn up(&self, client: &(dyn DynamoDb + Send + Sync)) -> Result<(), Error>;
+    async fn down(&self, client: &(dyn DynamoDb + Send + Sync)) -> Result<(), Error>;
+}
+
+/// Tracks which migrations have already been applied, kept separate from
+/// `Migration::up`/`down` so `run_migrations`/`rollback_migrations` can be
+/// unit tested with a mock rather than a live `dynamodb-local`.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait MigrationLedger: Sync {
+    async fn ensure_table(&self) -> Result<(), Error>;
+    async fn applied_versions(&self) -> Result<HashSet<String>, Error>;
+    async fn mark_applied(&self, version: &str) -> Result<(), Error>;
+    async fn mark_reverted(&self, version: &str) -> Result<(), Error>;
+}
+
+pub struct DynamoMigrationLedger<'a> {
+    client: &'a (dyn DynamoDb + Send + Sync),
+}
+
+impl<'a> DynamoMigrationLedger<'a> {
+    pub fn new(client: &'a (dyn DynamoDb + Send + Sync)) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl<'a> MigrationLedger for DynamoMigrationLedger<'a> {
+    async fn ensure_table(&self) -> Result<(), Error> {
+        // Best-effort: DynamoDB errors if the table already exists, which is
+        // the common case after the first run, so a failure here isn't fatal.
+        let _ = self
+            .client
+            .create_table(CreateTableInput {
+                table_name: MIGRATIONS_TABLE.into(),
+                key_schema: vec![KeySchemaElement {
+                    attribute_name: "version".into(),
+                    key_type: "HASH".into(),
+                }],
+                attribute_definitions: vec![AttributeDefinition {
+                    attribute_name: "version".into(),
+                    attribute_type: "S".into(),
+                }],
+                provisioned_throughput: Some(ProvisionedThroughput {
+                    read_capacity_units: 1,
+                    write_capacity_units: 1,
+                }),
+                ..CreateTableInput::default()
+            })
+            .await;
+        Ok(())
+    }
+
+    async fn applied_versions(&self) -> Result<HashSet<String>, Error> {
+        let output = self
+            .client
+            .scan(ScanInput {
+                table_name: MIGRATIONS_TABLE.into(),
+                ..ScanInput::default()
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(output
+            .items
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|item| item.get("version").and_then(|v| v.s.clone()))
+            .collect())
+    }
+
+    async fn mark_applied(&self, version: &str) -> Result<(), Error> {
+        let mut item = HashMap::new();
+        item.insert(
+            "version".to_string(),
+            AttributeValue {
+                s: Some(version.to_string()),
+                ..AttributeValue::default()
+            },
+        );
+
+        self.client
+            .put_item(PutItemInput {
+                table_name: MIGRATIONS_TABLE.into(),
+                item,
+                ..PutItemInput::default()
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    async fn mark_reverted(&self, version: &str) -> Result<(), Error> {
+        let mut key = HashMap::new();
+        key.insert(
+            "version".to_string(),
+            AttributeValue {
+                s: Some(version.to_string()),
+                ..AttributeValue::default()
+            },
+        );
+
+        self.client
+            .delete_item(DeleteItemInput {
+                table_name: MIGRATIONS_TABLE.into(),
+                key,
+                ..DeleteItemInput::default()
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+/// Creates the `_migrations` table if it doesn't exist yet, then applies every
+/// migration in `migrations` that isn't already recorded there, in declared
+/// order. Stops and returns the error on the first migration that fails to
+/// apply, leaving it (and everything after it) unrecorded so the next run
+/// retries it instead of treating it as done.
+pub async fn run_migrations(
+    ledger: &dyn MigrationLedger,
+    client: &(dyn DynamoDb + Send + Sync),
+    migrations: &[&dyn Migration],
+) -> Result<(), Error> {
+    ledger.ensure_table().await?;
+    let applied = ledger.applied_versions().await?;
+
+    for migration in migrations {
+        if applied.contains(migration.version()) {
+            continue;
+        }
+
+        println!("migrator: applying {}", migration.version());
+        migration.up(client).await?;
+        ledger.mark_applied(migration.version()).await?;
+    }
+
+    Ok(())
+}
+
+/// Reverses applied migrations newest-first, removing each version's row as
+/// it goes. Stops and returns the error on the first migration that fails to
+/// revert, leaving it recorded as applied so a retry picks up from there.
+pub async fn rollback_migrations(
+    ledger: &dyn MigrationLedger,
+    client: &(dyn DynamoDb + Send + Sync),
+    migrations: &[&dyn Migration],
+) -> Result<(), Error> {
+    let applied = ledger.applied_versions().await?;
+
+    for migration in migrations.iter().rev() {
+        if !applied.contains(migration.version()) {
+            continue;
+        }
+
+        println!("migrator: reverting {}", migration.version());
+        migration.down(client).await?;
+        ledger.mark_reverted(migration.version()).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StubMigration {
+        version: &'static str,
+        up_result: Result<(), &'static str>,
+    }
+
+    #[async_trait]
+    impl Migration for StubMigration {
+        fn version(&self) -> &str {
+            self.version
+        }
+
+        async fn up(&self, _client: &(dyn DynamoDb + Send + Sync)) -> Result<(), Error> {
+            self.up_result.map_err(Into::into)
+        }
+
+        async fn down(&self, _client: &(dyn DynamoDb + Send + Sync)) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn stops_and_does_not_mark_applied_when_a_migration_fails() {
+        let first = StubMigration {
+            version: "0001",
+            up_result: Err("boom"),
+        };
+        let second = StubMigration {
+            version: "0002",
+            up_result: Ok(()),
+        };
+        let migrations: Vec<&dyn Migration> = vec![&first, &second];
+
+        let mut ledger = MockMigrationLedger::new();
+        ledger.expect_ensure_table().returning(|| Ok(()));
+        ledger
+            .expect_applied_versions()
+            .returning(|| Ok(HashSet::new()));
+        ledger.expect_mark_applied().times(0).returning(|_| Ok(()));
+
+        let client = dynomite::dynamodb::DynamoDbClient::new(rusoto_core::Region::Custom {
+            name: "us-east-1".into(),
+            endpoint: "http://localhost:8000".into(),
+        });
+
+        let result = run_migrations(&ledger, &client, &migrations).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn skips_migrations_already_recorded_in_the_ledger() {
+        let migration = StubMigration {
+            version: "0001",
+            up_result: Ok(()),
+        };
+        let migrations: Vec<&dyn Migration> = vec![&migration];
+
+        let mut ledger = MockMigrationLedger::new();
+        ledger.expect_ensure_table().returning(|| Ok(()));
+        ledger
+            .expect_applied_versions()
+            .returning(|| Ok(HashSet::from(["0001".to_string()])));
+        ledger.expect_mark_applied().times(0).returning(|_| Ok(()));
+
+        let client = dynomite::dynamodb::DynamoDbClient::new(rusoto_core::Region::Custom {
+            name: "us-east-1".into(),
+            endpoint: "http://localhost:8000".into(),
+        });
+
+        let result = run_migrations(&ledger, &client, &migrations).await;
+        assert!(result.is_ok());
+    }
+}
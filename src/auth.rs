@@ -0,0 +1,159 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use dynomite::{
+    dynamodb::{DynamoDb, GetItemInput, PutItemInput},
+    FromAttributes, Item,
+};
+use lambda_http::Request;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use subtle::ConstantTimeEq;
+
+use crate::error::AppError;
+use crate::pool::DynamoPool;
+use crate::Error;
+
+const SESSIONS_TABLE: &str = "sessions";
+const SESSION_TTL_SECS: i64 = 60 * 60;
+
+#[derive(Item, Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    #[dynomite(partition_key)]
+    token: String,
+    user_id: String,
+    #[dynomite(rename = "expiresAt")]
+    expires_at: i64,
+}
+
+impl Session {
+    pub fn new(user_id: String) -> Self {
+        Self {
+            token: uuid::Uuid::new_v4().to_string(),
+            user_id,
+            expires_at: now() + SESSION_TTL_SECS,
+        }
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn expires_at(&self) -> i64 {
+        self.expires_at
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// Looks sessions up by their opaque token; `get_session` returns `None` only
+/// for tokens with no stored session and returns expired sessions as-is,
+/// leaving the expiry check to `authenticate`.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait SessionStore: Send + Sync {
+    async fn create_session(&self, session: &Session) -> Result<(), Error>;
+    async fn get_session(&self, token: &str) -> Result<Option<Session>, Error>;
+}
+
+/// Borrows a pooled client per request, same as `DynamoBookRepository`, so
+/// `authenticate()` never runs on a cold client built once at startup.
+pub struct DynamoSessionStore {
+    pool: DynamoPool,
+}
+
+impl DynamoSessionStore {
+    pub fn new(pool: DynamoPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SessionStore for DynamoSessionStore {
+    async fn create_session(&self, session: &Session) -> Result<(), Error> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        client
+            .put_item(PutItemInput {
+                table_name: SESSIONS_TABLE.to_string(),
+                item: session.clone().into(),
+                ..PutItemInput::default()
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn get_session(&self, token: &str) -> Result<Option<Session>, Error> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let mut key = HashMap::new();
+        key.insert(
+            "token".to_string(),
+            dynomite::AttributeValue {
+                s: Some(token.to_string()),
+                ..dynomite::AttributeValue::default()
+            },
+        );
+
+        let output = client
+            .get_item(GetItemInput {
+                table_name: SESSIONS_TABLE.to_string(),
+                key,
+                ..GetItemInput::default()
+            })
+            .await?;
+
+        match output.item {
+            Some(item) => Ok(Some(Session::from_attrs(item)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Gatekeeper for `POST /auth/genkey` itself: the caller must present the
+/// configured shared secret in the `x-genkey-secret` header before a session
+/// is minted for whatever `user_id` they ask for. `genkey_handler` treats a
+/// missing/empty `shared_secret` (i.e. `GENKEY_SHARED_SECRET` unset) as
+/// "reject everyone" rather than "skip the check".
+pub fn authorize_genkey(request: &Request, shared_secret: &str) -> Result<(), AppError> {
+    let provided = request
+        .headers()
+        .get("x-genkey-secret")
+        .and_then(|v| v.to_str().ok())
+        .ok_or(AppError::Unauthorized)?;
+
+    if provided.as_bytes().ct_eq(shared_secret.as_bytes()).unwrap_u8() == 0 {
+        return Err(AppError::Unauthorized);
+    }
+
+    Ok(())
+}
+
+/// Reads the `Authorization` header, looks up the session it names, and
+/// rejects with `AppError::Unauthorized` when it's missing, unknown, or expired.
+pub async fn authenticate(
+    sessions: &dyn SessionStore,
+    request: &Request,
+) -> Result<Session, AppError> {
+    let token = request
+        .headers()
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim_start_matches("Bearer ").to_string())
+        .filter(|v| !v.is_empty())
+        .ok_or(AppError::Unauthorized)?;
+
+    let session = sessions
+        .get_session(&token)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    if session.expires_at < now() {
+        return Err(AppError::Unauthorized);
+    }
+
+    Ok(session)
+}
@@ -0,0 +1,32 @@
+use utoipa::OpenApi;
+
+use crate::{
+    BookEntity, CoverUrlResponse, CreateBookRequest, ErrorResponse, ErrorType,
+    GenerateKeyRequest, GenerateKeyResponse, UpdateBookRequest,
+};
+
+/// Self-describing contract for the books API, served at `GET /openapi.json`.
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        crate::hello,
+        crate::list_books_handler,
+        crate::create_book,
+        crate::update_book,
+        crate::delete_book_handler,
+        crate::upload_cover_handler,
+        crate::get_cover_handler,
+        crate::genkey_handler,
+    ),
+    components(schemas(
+        BookEntity,
+        ErrorResponse,
+        ErrorType,
+        CreateBookRequest,
+        UpdateBookRequest,
+        CoverUrlResponse,
+        GenerateKeyRequest,
+        GenerateKeyResponse,
+    ))
+)]
+pub struct ApiDoc;
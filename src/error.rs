@@ -0,0 +1,79 @@
+use dynomite::AttributeError;
+use lambda_http::{http::StatusCode, Response};
+use rusoto_core::RusotoError;
+use thiserror::Error;
+
+use crate::{Error, ErrorResponse, ErrorType};
+
+/// Every way a handler can fail, mapped to the right HTTP status and
+/// `ErrorResponse` so no handler path needs to panic or unwrap its way
+/// to a response.
+#[derive(Error, Debug)]
+pub enum AppError {
+    #[error("invalid id: {0}")]
+    InvalidId(#[from] uuid::Error),
+    #[error("invalid request: {0:?}")]
+    BadRequest(Vec<String>),
+    #[error("book not found")]
+    NotFound,
+    #[error("missing or expired session key")]
+    Unauthorized,
+    #[error("dynamodb request failed: {0}")]
+    Dynamo(#[source] Error),
+    #[error("failed to deserialize book: {0}")]
+    Serialization(#[from] AttributeError),
+}
+
+impl AppError {
+    pub fn into_response(self, request_id: String) -> Response<String> {
+        let (status, error_type, error_codes) = match &self {
+            AppError::InvalidId(_) => (
+                StatusCode::BAD_REQUEST,
+                ErrorType::RequestInvalid,
+                Some(vec!["id".to_string()]),
+            ),
+            AppError::BadRequest(error_codes) => (
+                StatusCode::BAD_REQUEST,
+                ErrorType::RequestInvalid,
+                Some(error_codes.clone()),
+            ),
+            AppError::NotFound => (StatusCode::NOT_FOUND, ErrorType::NotFound, None),
+            AppError::Unauthorized => (
+                StatusCode::UNAUTHORIZED,
+                ErrorType::RequestUnauthorized,
+                None,
+            ),
+            AppError::Dynamo(_) | AppError::Serialization(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ErrorType::InternalServerError,
+                None,
+            ),
+        };
+
+        let body = ErrorResponse {
+            request_id,
+            error_type,
+            error_codes,
+        };
+
+        Response::builder()
+            .status(status)
+            .body(serde_json::to_string(&body).unwrap())
+            .unwrap()
+    }
+}
+
+impl From<Error> for AppError {
+    fn from(e: Error) -> Self {
+        AppError::Dynamo(e)
+    }
+}
+
+impl<E> From<RusotoError<E>> for AppError
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn from(e: RusotoError<E>) -> Self {
+        AppError::Dynamo(Box::new(e))
+    }
+}
@@ -4,40 +4,50 @@
 /// ```bash
 /// $ docker run -p 8000:8000 amazon/dynamodb-local
 /// ```
-use std::{collections::HashMap, env};
+use std::{env, sync::Arc};
 
-use dynomite::{
-    dynamodb::{DynamoDb, DynamoDbClient, GetItemInput},
-    retry::Policy,
-    retry::RetryingDynamoDb,
-    AttributeError, FromAttributes, Item, Retries,
-};
+use dynomite::Item;
 use lambda_http::{
-    handler, http::StatusCode, lambda, Context, IntoResponse, Request, RequestExt, Response,
+    handler,
+    http::{Method, StatusCode},
+    lambda_runtime as lambda, Body, Context, Request, RequestExt, Response,
+};
+use auth::{authenticate, authorize_genkey, DynamoSessionStore, Session, SessionStore};
+use docs::ApiDoc;
+use error::AppError;
+use media::{
+    boundary_from_content_type, covers_bucket, create_s3_client, first_part, resolve_credentials,
+    CoverStore, S3CoverStore,
 };
-use rusoto_core::Region;
+use pool::create_pool;
+use repository::{BookRepository, DynamoBookRepository};
 use serde::{Deserialize, Serialize};
+use utoipa::{OpenApi, ToSchema};
 use uuid::Uuid;
 #[macro_use]
-extern crate lazy_static;
-#[macro_use]
 #[cfg(not(test))]
 extern crate log;
 
-lazy_static! {
-    static ref DB: RetryingDynamoDb<DynamoDbClient> = create_client();
-}
+mod auth;
+mod docs;
+mod error;
+mod media;
+mod pool;
+mod repository;
 
-#[derive(Item, Debug, Clone, Serialize, Deserialize)]
+#[derive(Item, Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct BookEntity {
     #[dynomite(partition_key)]
     #[serde(with = "json_uuid")]
     id: Uuid,
     #[dynomite(rename = "bookTitle", default)]
     title: String,
+    #[dynomite(rename = "coverKey", default)]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    cover_key: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ErrorType {
     RequestInvalid,
@@ -46,7 +56,7 @@ pub enum ErrorType {
     InternalServerError,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct ErrorResponse {
     request_id: String,
     error_type: ErrorType,
@@ -70,6 +80,24 @@ impl ErrorResponse {
             error_codes: None,
         }
     }
+
+    pub fn invalid(request_id: String, error_codes: Vec<String>) -> Self {
+        Self {
+            request_id,
+            error_type: ErrorType::RequestInvalid,
+            error_codes: Some(error_codes),
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateBookRequest {
+    title: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateBookRequest {
+    title: String,
 }
 
 mod json_uuid {
@@ -88,28 +116,11 @@ mod json_uuid {
         S: Deserializer<'de>,
     {
         let uuid = String::deserialize(deserializer)?;
-        Ok::<_, S::Error>(Uuid::parse_str(uuid.as_str()).unwrap())
+        Uuid::parse_str(uuid.as_str()).map_err(serde::de::Error::custom)
     }
 }
 
-type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
-
-pub fn create_client() -> RetryingDynamoDb<DynamoDbClient> {
-    let local_client = DynamoDbClient::new(Region::Custom {
-        name: "us-east-1".into(),
-        endpoint: "http://localhost:8000".into(),
-    })
-    .with_retries(Policy::default());
-
-    let remote_client = DynamoDbClient::new(Region::EuWest2).with_retries(Policy::default());
-
-    let env = env::var("ENV").unwrap_or("".into());
-
-    match env == "live" {
-        true => remote_client,
-        _ => local_client
-    }
-}
+pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
 
 pub fn not_found(request_id: String) -> Response<String> {
     Response::builder()
@@ -118,69 +129,405 @@ pub fn not_found(request_id: String) -> Response<String> {
         .unwrap()
 }
 
-fn internal_server(request_id: String) -> Response<String> {
-    Response::builder()
-        .status(StatusCode::INTERNAL_SERVER_ERROR)
-        .body(serde_json::to_string(&ErrorResponse::internal_server(request_id)).unwrap())
-        .unwrap()
+fn body_json<'a, T>(body: &'a Body) -> Option<T>
+where
+    T: Deserialize<'a>,
+{
+    let text = match body {
+        Body::Text(e) => e.as_str(),
+        _ => return None,
+    };
+
+    serde_json::from_str(text).ok()
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     env_logger::init();
-    lambda::run(handler(hello)).await?;
+
+    let pool = create_pool()?;
+    let repo: Arc<dyn BookRepository> = Arc::new(DynamoBookRepository::new(pool.clone()));
+    let sessions: Arc<dyn SessionStore> = Arc::new(DynamoSessionStore::new(pool));
+
+    let (s3_client, s3_region) = create_s3_client();
+    let s3_credentials = resolve_credentials().await?;
+    let covers: Arc<dyn CoverStore> = Arc::new(S3CoverStore::new(
+        s3_client,
+        s3_region,
+        s3_credentials,
+        covers_bucket(),
+    ));
+
+    lambda::run(handler(move |request: Request, context: Context| {
+        let repo = repo.clone();
+        let sessions = sessions.clone();
+        let covers = covers.clone();
+        async move {
+            router(
+                repo.as_ref(),
+                sessions.as_ref(),
+                covers.as_ref(),
+                request,
+                context,
+            )
+            .await
+        }
+    }))
+    .await?;
     Ok(())
 }
 
-async fn hello(request: Request, context: Context) -> Result<impl IntoResponse, Error> {
+async fn router(
+    repo: &dyn BookRepository,
+    sessions: &dyn SessionStore,
+    covers: &dyn CoverStore,
+    request: Request,
+    context: Context,
+) -> Result<Response<String>, Error> {
+    if request.method() == Method::GET && request.uri().path() == "/openapi.json" {
+        return Ok(openapi_spec());
+    }
+
+    let request_id = context.request_id.clone();
+
+    if request.method() == Method::POST && request.uri().path() == "/auth/genkey" {
+        let shared_secret = env::var("GENKEY_SHARED_SECRET").unwrap_or_default();
+        return genkey_handler(sessions, &shared_secret, request, context)
+            .await
+            .or_else(|e| Ok(e.into_response(request_id)));
+    }
+
+    if let Err(e) = authenticate(sessions, &request).await {
+        return Ok(e.into_response(request_id));
+    }
+
+    let has_id = request.path_parameters().get("id").is_some();
+    let is_cover = request.uri().path().ends_with("/cover");
+
+    match (request.method().clone(), has_id, is_cover) {
+        (Method::PUT, true, true) => upload_cover_handler(repo, covers, request, context)
+            .await
+            .or_else(|e| Ok(e.into_response(request_id))),
+        (Method::GET, true, true) => get_cover_handler(repo, covers, request, context)
+            .await
+            .or_else(|e| Ok(e.into_response(request_id))),
+        (Method::GET, true, false) => hello(repo, request, context)
+            .await
+            .or_else(|e| Ok(e.into_response(request_id))),
+        (Method::GET, false, false) => list_books_handler(repo, request, context)
+            .await
+            .or_else(|e| Ok(e.into_response(request_id))),
+        (Method::POST, false, false) => create_book(repo, request, context)
+            .await
+            .or_else(|e| Ok(e.into_response(request_id))),
+        (Method::PUT, true, false) => update_book(repo, request, context)
+            .await
+            .or_else(|e| Ok(e.into_response(request_id))),
+        (Method::DELETE, true, false) => delete_book_handler(repo, request, context)
+            .await
+            .or_else(|e| Ok(e.into_response(request_id))),
+        _ => Ok(not_found(context.request_id)),
+    }
+}
+
+fn openapi_spec() -> Response<String> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("content-type", "application/json")
+        .body(ApiDoc::openapi().to_json().unwrap())
+        .unwrap()
+}
+
+#[utoipa::path(
+    get,
+    path = "/books",
+    responses(
+        (status = 200, description = "All books", body = [BookEntity]),
+    )
+)]
+async fn list_books_handler(
+    repo: &dyn BookRepository,
+    _request: Request,
+    _context: Context,
+) -> Result<Response<String>, AppError> {
+    let books = repo.list_books().await?;
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .body(serde_json::to_string(&books).unwrap())
+        .unwrap())
+}
+
+#[utoipa::path(
+    post,
+    path = "/books",
+    request_body = CreateBookRequest,
+    responses(
+        (status = 201, description = "Book created", body = BookEntity),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+    )
+)]
+async fn create_book(
+    repo: &dyn BookRepository,
+    request: Request,
+    _context: Context,
+) -> Result<Response<String>, AppError> {
+    let payload: CreateBookRequest =
+        body_json(request.body()).ok_or_else(|| AppError::BadRequest(vec!["title".to_string()]))?;
+
+    let book = BookEntity {
+        id: Uuid::new_v4(),
+        title: payload.title,
+        cover_key: None,
+    };
+
+    info!("main: creating book {:?}", book);
+
+    repo.put_book(&book).await?;
+
+    Ok(Response::builder()
+        .status(StatusCode::CREATED)
+        .body(serde_json::to_string(&book).unwrap())
+        .unwrap())
+}
+
+#[utoipa::path(
+    put,
+    path = "/books/{id}",
+    params(("id" = String, Path, description = "Book id")),
+    request_body = UpdateBookRequest,
+    responses(
+        (status = 200, description = "Book updated", body = BookEntity),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+    )
+)]
+async fn update_book(
+    repo: &dyn BookRepository,
+    request: Request,
+    _context: Context,
+) -> Result<Response<String>, AppError> {
+    let qs = request.path_parameters();
+    let key = qs.get("id").unwrap_or("").to_string();
+
+    let payload: UpdateBookRequest =
+        body_json(request.body()).ok_or_else(|| AppError::BadRequest(vec!["title".to_string()]))?;
+
+    let id = Uuid::parse_str(key.as_str())?;
+
+    let cover_key = repo.get_book(&key).await?.ok_or(AppError::NotFound)?.cover_key;
+
+    let book = BookEntity {
+        id,
+        title: payload.title,
+        cover_key,
+    };
+
+    info!("main: updating book {:?}", book);
+
+    repo.put_book(&book).await?;
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .body(serde_json::to_string(&book).unwrap())
+        .unwrap())
+}
+
+#[utoipa::path(
+    delete,
+    path = "/books/{id}",
+    params(("id" = String, Path, description = "Book id")),
+    responses(
+        (status = 204, description = "Book deleted"),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+async fn delete_book_handler(
+    repo: &dyn BookRepository,
+    request: Request,
+    _context: Context,
+) -> Result<Response<String>, AppError> {
     let qs = request.path_parameters();
-    let key = qs.get("id").or_else(|| Some("")).unwrap();
+    let key = qs.get("id").unwrap_or("");
+
+    info!("main: deleting book {:?}", key);
+
+    repo.delete_book(key).await?;
+
+    Ok(Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .body(String::new())
+        .unwrap())
+}
+
+#[utoipa::path(
+    get,
+    path = "/books/{id}",
+    params(("id" = String, Path, description = "Book id")),
+    responses(
+        (status = 200, description = "Book found", body = BookEntity),
+        (status = 404, description = "Book not found", body = ErrorResponse),
+    )
+)]
+async fn hello(
+    repo: &dyn BookRepository,
+    request: Request,
+    _context: Context,
+) -> Result<Response<String>, AppError> {
+    let qs = request.path_parameters();
+    let key = qs.get("id").unwrap_or("");
 
     info!("main: get by id: {:?}", key);
 
-    let attr = dynomite::AttributeValue {
-        s: Some(key.to_string()),
-        ..dynomite::AttributeValue::default()
+    let book = repo.get_book(key).await?.ok_or(AppError::NotFound)?;
+
+    info!("main: fetched book, found {:?}", book);
+
+    Ok(Response::builder()
+        .status(200)
+        .header("x-foo-bar", "bar")
+        .header("x-bar-baz", "baz")
+        .body(serde_json::to_string(&book).unwrap())
+        .unwrap())
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CoverUrlResponse {
+    url: String,
+}
+
+#[utoipa::path(
+    put,
+    path = "/books/{id}/cover",
+    params(("id" = String, Path, description = "Book id")),
+    responses(
+        (status = 200, description = "Cover uploaded", body = BookEntity),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Book not found", body = ErrorResponse),
+    )
+)]
+async fn upload_cover_handler(
+    repo: &dyn BookRepository,
+    covers: &dyn CoverStore,
+    request: Request,
+    _context: Context,
+) -> Result<Response<String>, AppError> {
+    let qs = request.path_parameters();
+    let key = qs.get("id").unwrap_or("").to_string();
+
+    let mut book = repo.get_book(&key).await?.ok_or(AppError::NotFound)?;
+
+    let content_type_header = request
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+
+    let boundary = boundary_from_content_type(content_type_header)
+        .ok_or_else(|| AppError::BadRequest(vec!["content-type".to_string()]))?;
+
+    let body_bytes: Vec<u8> = match request.body() {
+        Body::Text(t) => t.as_bytes().to_vec(),
+        Body::Binary(b) => b.clone(),
+        Body::Empty => Vec::new(),
     };
 
-    let mut map = HashMap::new();
-    map.insert("id".to_string(), attr);
-    let book_raw_item = DB
-        .get_item(GetItemInput {
-            table_name: "books".to_string(),
-            key: map,
-            ..GetItemInput::default()
-        })
-        .await;
-
-    match book_raw_item {
-        Err(e) => {
-            info!("dynamodb failed: {:?}", e);
-            Ok(internal_server(context.request_id))
-        }
-        Ok(b) => {
-            info!("main: fetched book, found {:?}", b);
-
-            let try_book: Option<Result<BookEntity, AttributeError>> =
-                b.item.map(BookEntity::from_attrs);
-
-            if try_book.is_some() {
-                let book_result = try_book.unwrap();
-                let books: BookEntity = book_result.expect("result no work");
-                info!("main: parsing to entity {:?}", books);
-
-                let r = Response::builder()
-                    .status(200)
-                    .header("x-foo-bar", "bar")
-                    .header("x-bar-baz", "baz")
-                    .body(serde_json::to_string(&books).unwrap())
-                    .unwrap();
-                Ok::<_, Error>(r)
-            } else {
-                Ok(not_found(context.request_id))
-            }
-        }
+    let (content_type, bytes) = first_part(&body_bytes, &boundary)
+        .ok_or_else(|| AppError::BadRequest(vec!["cover".to_string()]))?;
+
+    if !content_type.starts_with("image/") {
+        return Err(AppError::BadRequest(vec!["content-type".to_string()]));
     }
+
+    let cover_key = format!("covers/{}/{}", key, Uuid::new_v4());
+
+    covers.upload_cover(&cover_key, &content_type, bytes).await?;
+
+    book.cover_key = Some(cover_key);
+
+    repo.put_book(&book).await?;
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .body(serde_json::to_string(&book).unwrap())
+        .unwrap())
+}
+
+#[utoipa::path(
+    get,
+    path = "/books/{id}/cover",
+    params(("id" = String, Path, description = "Book id")),
+    responses(
+        (status = 200, description = "Presigned cover download URL", body = CoverUrlResponse),
+        (status = 404, description = "Book or cover not found", body = ErrorResponse),
+    )
+)]
+async fn get_cover_handler(
+    repo: &dyn BookRepository,
+    covers: &dyn CoverStore,
+    request: Request,
+    _context: Context,
+) -> Result<Response<String>, AppError> {
+    let qs = request.path_parameters();
+    let key = qs.get("id").unwrap_or("");
+
+    let book = repo.get_book(key).await?.ok_or(AppError::NotFound)?;
+    let cover_key = book.cover_key.ok_or(AppError::NotFound)?;
+
+    let url = covers.presigned_download_url(&cover_key);
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .body(serde_json::to_string(&CoverUrlResponse { url }).unwrap())
+        .unwrap())
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct GenerateKeyRequest {
+    user_id: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct GenerateKeyResponse {
+    token: String,
+    expires_at: i64,
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/genkey",
+    request_body = GenerateKeyRequest,
+    responses(
+        (status = 201, description = "Session key issued", body = GenerateKeyResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+    )
+)]
+async fn genkey_handler(
+    sessions: &dyn SessionStore,
+    shared_secret: &str,
+    request: Request,
+    _context: Context,
+) -> Result<Response<String>, AppError> {
+    if shared_secret.is_empty() {
+        return Err(AppError::Unauthorized);
+    }
+    authorize_genkey(&request, shared_secret)?;
+
+    let payload: GenerateKeyRequest =
+        body_json(request.body()).ok_or_else(|| AppError::BadRequest(vec!["user_id".to_string()]))?;
+
+    let session = Session::new(payload.user_id);
+    sessions.create_session(&session).await?;
+
+    Ok(Response::builder()
+        .status(StatusCode::CREATED)
+        .body(
+            serde_json::to_string(&GenerateKeyResponse {
+                token: session.token().to_string(),
+                expires_at: session.expires_at(),
+            })
+            .unwrap(),
+        )
+        .unwrap())
 }
 
 #[cfg(test)]
@@ -188,41 +535,13 @@ use std::println as info;
 
 #[cfg(test)]
 mod tests {
-    use dynomite::AttributeValue;
-    use lambda_http::{http::Request, Body, Context, IntoResponse, StrMap};
-    use rusoto_core::RusotoError;
-    use rusoto_dynamodb::{
-        DeleteItemError, DeleteItemInput, DeleteItemOutput, PutItemError, PutItemInput,
-        PutItemOutput,
-    };
+    use lambda_http::{http::Request, Context, IntoResponse, StrMap};
+    use mockall::predicate::eq;
+    use std::collections::HashMap;
 
     use super::*;
-
-    pub async fn insert_book(
-        client: &RetryingDynamoDb<DynamoDbClient>,
-        book: &BookEntity,
-    ) -> Result<PutItemOutput, RusotoError<PutItemError>> {
-        client
-            .put_item(PutItemInput {
-                table_name: "books".to_string(),
-                item: book.clone().into(),
-                ..PutItemInput::default()
-            })
-            .await
-    }
-
-    pub async fn delete_book(
-        client: &RetryingDynamoDb<DynamoDbClient>,
-        key: HashMap<String, AttributeValue>,
-    ) -> Result<DeleteItemOutput, RusotoError<DeleteItemError>> {
-        client
-            .delete_item(DeleteItemInput {
-                table_name: "books".to_string(),
-                key,
-                ..DeleteItemInput::default()
-            })
-            .await
-    }
+    use auth::MockSessionStore;
+    use repository::MockBookRepository;
 
     pub fn get_body<'a, T>(body: &'a Body) -> T
     where
@@ -238,48 +557,176 @@ mod tests {
 
     #[tokio::test]
     async fn hello_handles() {
-        let mut hash = HashMap::new();
-
         let rust_book = BookEntity {
             id: Uuid::new_v4(),
             title: "rust".into(),
+            cover_key: None,
         };
 
-        insert_book(&DB, &rust_book).await.unwrap();
+        let mut repo = MockBookRepository::new();
+        let expected = rust_book.clone();
+        let book_id = rust_book.id.to_string();
+        repo.expect_get_book()
+            .with(eq(book_id.clone()))
+            .returning(move |_| Ok(Some(expected.clone())));
 
-        hash.insert("id".to_string(), vec![rust_book.id.to_string()]);
+        let mut hash = HashMap::new();
+        hash.insert("id".to_string(), vec![book_id]);
         let request = Request::<Body>::default().with_path_parameters(StrMap::from(hash));
 
-        let response = hello(request, Context::default())
+        let response = hello(&repo, request, Context::default())
             .await
             .expect("Did not work")
             .into_response();
 
-        let b: BookEntity = get_body(&response.body());
+        let b: BookEntity = get_body(response.body());
 
         assert_eq!(b.title, "rust");
         assert_eq!(response.status(), 200);
         assert_eq!(response.headers().get("x-foo-bar").unwrap(), "bar");
         assert_eq!(response.headers().get("x-bar-baz").unwrap(), "baz");
-        delete_book(&DB, rust_book.key()).await.unwrap();
     }
 
     #[tokio::test]
     async fn hello_handles_not_found() {
-        let mut hash = HashMap::new();
+        let mut repo = MockBookRepository::new();
+        repo.expect_get_book()
+            .with(eq("foo-bar"))
+            .returning(|_| Ok(None));
 
+        let mut hash = HashMap::new();
         hash.insert("id".to_string(), vec!["foo-bar".to_string()]);
         let request = Request::<Body>::default().with_path_parameters(StrMap::from(hash));
 
-        let response = hello(request, Context::default())
+        let response = hello(&repo, request, Context::default())
             .await
-            .expect("Did not work")
+            .expect_err("expected a not-found error")
+            .into_response("req-1".to_string())
             .into_response();
 
-        let b: ErrorResponse = get_body(&response.body());
+        let b: ErrorResponse = get_body(response.body());
 
         assert_eq!(b.error_type, ErrorType::NotFound);
         assert_eq!(b.error_codes, None);
         assert_eq!(response.status(), 404);
     }
+
+    #[tokio::test]
+    async fn hello_handles_dynamo_errors_as_500() {
+        let mut repo = MockBookRepository::new();
+        repo.expect_get_book()
+            .with(eq("foo-bar"))
+            .returning(|_| Err("dynamodb unavailable".into()));
+
+        let mut hash = HashMap::new();
+        hash.insert("id".to_string(), vec!["foo-bar".to_string()]);
+        let request = Request::<Body>::default().with_path_parameters(StrMap::from(hash));
+
+        let response = hello(&repo, request, Context::default())
+            .await
+            .expect_err("expected a dynamo error")
+            .into_response("req-1".to_string())
+            .into_response();
+
+        let b: ErrorResponse = get_body(response.body());
+
+        assert_eq!(b.error_type, ErrorType::InternalServerError);
+        assert_eq!(response.status(), 500);
+    }
+
+    #[tokio::test]
+    async fn create_book_handles() {
+        let mut repo = MockBookRepository::new();
+        repo.expect_put_book().returning(|_| Ok(()));
+
+        let body = Body::from(r#"{"title":"rust"}"#);
+        let request = Request::<Body>::new(body);
+
+        let response = create_book(&repo, request, Context::default())
+            .await
+            .expect("Did not work")
+            .into_response();
+
+        let b: BookEntity = get_body(response.body());
+
+        assert_eq!(b.title, "rust");
+        assert_eq!(response.status(), 201);
+    }
+
+    #[tokio::test]
+    async fn delete_book_handler_handles() {
+        let rust_book_id = Uuid::new_v4().to_string();
+
+        let mut repo = MockBookRepository::new();
+        repo.expect_delete_book()
+            .with(eq(rust_book_id.clone()))
+            .returning(|_| Ok(()));
+
+        let mut hash = HashMap::new();
+        hash.insert("id".to_string(), vec![rust_book_id]);
+        let request = Request::<Body>::default().with_path_parameters(StrMap::from(hash));
+
+        let response = delete_book_handler(&repo, request, Context::default())
+            .await
+            .expect("Did not work")
+            .into_response();
+
+        assert_eq!(response.status(), 204);
+    }
+
+    #[tokio::test]
+    async fn genkey_handler_handles() {
+        let mut sessions = MockSessionStore::new();
+        sessions.expect_create_session().returning(|_| Ok(()));
+
+        let body = Body::from(r#"{"user_id":"alice"}"#);
+        let request = Request::builder()
+            .header("x-genkey-secret", "test-secret")
+            .body(body)
+            .unwrap();
+
+        let response = genkey_handler(&sessions, "test-secret", request, Context::default())
+            .await
+            .expect("Did not work")
+            .into_response();
+
+        let b: GenerateKeyResponse = get_body(response.body());
+
+        assert!(!b.token.is_empty());
+        assert_eq!(response.status(), 201);
+    }
+
+    #[tokio::test]
+    async fn genkey_handler_rejects_wrong_secret() {
+        let sessions = MockSessionStore::new();
+        let body = Body::from(r#"{"user_id":"alice"}"#);
+        let request = Request::builder()
+            .header("x-genkey-secret", "wrong")
+            .body(body)
+            .unwrap();
+
+        let err = genkey_handler(&sessions, "test-secret", request, Context::default())
+            .await
+            .expect_err("expected an unauthorized error");
+
+        let response = err.into_response("req-1".to_string()).into_response();
+
+        assert_eq!(response.status(), 401);
+    }
+
+    #[tokio::test]
+    async fn authenticate_rejects_missing_header() {
+        let sessions = MockSessionStore::new();
+        let request = Request::<Body>::default();
+
+        let err = authenticate(&sessions, &request)
+            .await
+            .expect_err("expected an unauthorized error");
+
+        let response = err.into_response("req-1".to_string()).into_response();
+        let b: ErrorResponse = get_body(response.body());
+
+        assert_eq!(b.error_type, ErrorType::RequestUnauthorized);
+        assert_eq!(response.status(), 401);
+    }
 }
@@ -0,0 +1,217 @@
+use std::env;
+
+use async_trait::async_trait;
+use rusoto_core::{
+    credential::{AwsCredentials, DefaultCredentialsProvider, ProvideAwsCredentials, StaticProvider},
+    HttpClient, Region,
+};
+use rusoto_s3::{util::PreSignedRequest, GetObjectRequest, PutObjectRequest, S3Client, S3};
+
+use crate::Error;
+
+const PRESIGNED_URL_TTL_SECS: u64 = 15 * 60;
+
+/// Uploads go straight to the configured bucket; downloads are served as a
+/// presigned URL rather than proxying the image bytes through the lambda.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait CoverStore: Send + Sync {
+    async fn upload_cover(&self, key: &str, content_type: &str, bytes: Vec<u8>) -> Result<(), Error>;
+    fn presigned_download_url(&self, key: &str) -> String;
+}
+
+pub struct S3CoverStore {
+    client: S3Client,
+    region: Region,
+    credentials: AwsCredentials,
+    bucket: String,
+}
+
+impl S3CoverStore {
+    pub fn new(client: S3Client, region: Region, credentials: AwsCredentials, bucket: String) -> Self {
+        Self {
+            client,
+            region,
+            credentials,
+            bucket,
+        }
+    }
+}
+
+#[async_trait]
+impl CoverStore for S3CoverStore {
+    async fn upload_cover(&self, key: &str, content_type: &str, bytes: Vec<u8>) -> Result<(), Error> {
+        self.client
+            .put_object(PutObjectRequest {
+                bucket: self.bucket.clone(),
+                key: key.to_string(),
+                content_type: Some(content_type.to_string()),
+                body: Some(bytes.into()),
+                ..PutObjectRequest::default()
+            })
+            .await?;
+        Ok(())
+    }
+
+    fn presigned_download_url(&self, key: &str) -> String {
+        let request = GetObjectRequest {
+            bucket: self.bucket.clone(),
+            key: key.to_string(),
+            ..GetObjectRequest::default()
+        };
+
+        request.get_presigned_url(
+            &self.region,
+            &self.credentials,
+            &rusoto_s3::util::PreSignedRequestOption {
+                expires_in: std::time::Duration::from_secs(PRESIGNED_URL_TTL_SECS),
+            },
+        )
+    }
+}
+
+/// Local-vs-live switch: a MinIO/LocalStack endpoint for development,
+/// `EuWest2` when `ENV=live`.
+pub fn create_s3_client() -> (S3Client, Region) {
+    let env = env::var("ENV").unwrap_or_default();
+
+    if env == "live" {
+        let region = Region::EuWest2;
+        return (S3Client::new(region.clone()), region);
+    }
+
+    let region = Region::Custom {
+        name: "us-east-1".into(),
+        endpoint: "http://localhost:9000".into(),
+    };
+
+    let client = S3Client::new_with(
+        HttpClient::new().unwrap(),
+        StaticProvider::new_minimal("minioadmin".into(), "minioadmin".into()),
+        region.clone(),
+    );
+
+    (client, region)
+}
+
+/// Resolves the credentials used to sign presigned cover-download URLs: the
+/// same MinIO keys as `create_s3_client` locally, the instance/execution
+/// role's credentials when `ENV=live`.
+pub async fn resolve_credentials() -> Result<AwsCredentials, Error> {
+    let env = env::var("ENV").unwrap_or_default();
+
+    if env == "live" {
+        let provider = DefaultCredentialsProvider::new()?;
+        return Ok(provider.credentials().await?);
+    }
+
+    Ok(AwsCredentials::new("minioadmin", "minioadmin", None, None))
+}
+
+pub fn covers_bucket() -> String {
+    env::var("COVERS_BUCKET").unwrap_or_else(|_| "book-covers".into())
+}
+
+/// A hand-rolled reader for the one thing we need out of a multipart body: the
+/// bytes and content type of its first file part. Good enough for a single
+/// cover-image upload; not a general-purpose multipart parser.
+pub fn first_part(body: &[u8], boundary: &str) -> Option<(String, Vec<u8>)> {
+    let delimiter = format!("--{}", boundary).into_bytes();
+    let mut sections = body
+        .windows(delimiter.len())
+        .enumerate()
+        .filter(|(_, w)| *w == delimiter.as_slice())
+        .map(|(i, _)| i);
+
+    let start = sections.next()?;
+    let end = sections.next()?;
+    let part = &body[start + delimiter.len()..end];
+
+    let header_end = part.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let headers = std::str::from_utf8(&part[..header_end]).ok()?;
+    let content = &part[header_end + 4..];
+    let content = content.strip_suffix(b"\r\n").unwrap_or(content);
+
+    let content_type = headers
+        .lines()
+        .find_map(|line| line.strip_prefix("Content-Type: ").or_else(|| line.strip_prefix("content-type: ")))
+        .unwrap_or("application/octet-stream")
+        .trim()
+        .to_string();
+
+    Some((content_type, content.to_vec()))
+}
+
+pub fn boundary_from_content_type(content_type: &str) -> Option<String> {
+    content_type
+        .split(';')
+        .map(str::trim)
+        .find_map(|part| part.strip_prefix("boundary="))
+        .map(|b| b.trim_matches('"').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boundary_from_content_type_handles_quoted_and_unquoted_boundaries() {
+        assert_eq!(
+            boundary_from_content_type(r#"multipart/form-data; boundary="XYZ123""#),
+            Some("XYZ123".to_string())
+        );
+        assert_eq!(
+            boundary_from_content_type("multipart/form-data; boundary=XYZ123"),
+            Some("XYZ123".to_string())
+        );
+        assert_eq!(boundary_from_content_type("application/json"), None);
+    }
+
+    #[test]
+    fn first_part_reads_a_single_part_body() {
+        let boundary = "XYZ123";
+        let body = format!(
+            "--{boundary}\r\nContent-Type: image/png\r\n\r\nfake-bytes\r\n--{boundary}--\r\n"
+        );
+
+        let (content_type, content) = first_part(body.as_bytes(), boundary).unwrap();
+
+        assert_eq!(content_type, "image/png");
+        assert_eq!(content, b"fake-bytes");
+    }
+
+    #[test]
+    fn first_part_falls_back_to_octet_stream_when_content_type_header_is_missing() {
+        let boundary = "XYZ123";
+        let body = format!("--{boundary}\r\n\r\nfake-bytes\r\n--{boundary}--\r\n");
+
+        let (content_type, content) = first_part(body.as_bytes(), boundary).unwrap();
+
+        assert_eq!(content_type, "application/octet-stream");
+        assert_eq!(content, b"fake-bytes");
+    }
+
+    #[test]
+    fn first_part_falls_back_to_octet_stream_when_content_type_header_is_garbled() {
+        let boundary = "XYZ123";
+        let body =
+            format!("--{boundary}\r\nNotAHeader\r\n\r\nfake-bytes\r\n--{boundary}--\r\n");
+
+        let (content_type, _) = first_part(body.as_bytes(), boundary).unwrap();
+
+        assert_eq!(content_type, "application/octet-stream");
+    }
+
+    #[test]
+    fn first_part_returns_none_without_a_second_boundary() {
+        let boundary = "XYZ123";
+        let body = format!("--{boundary}\r\nContent-Type: image/png\r\n\r\nfake-bytes");
+
+        assert_eq!(first_part(body.as_bytes(), boundary), None);
+    }
+
+    #[test]
+    fn first_part_returns_none_for_an_empty_body() {
+        assert_eq!(first_part(b"", "XYZ123"), None);
+    }
+}
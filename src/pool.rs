@@ -0,0 +1,88 @@
+use std::env;
+
+use async_trait::async_trait;
+use deadpool::managed::{self, Metrics, RecycleError, RecycleResult};
+use dynomite::{
+    dynamodb::{DynamoDb, DynamoDbClient, ListTablesInput},
+    retry::{Policy, RetryingDynamoDb},
+    Retries,
+};
+use rusoto_core::Region;
+
+use crate::Error;
+
+/// Builds fresh `RetryingDynamoDb` clients for the pool and, when
+/// `verify_on_recycle` is set, health-checks an idle client with a cheap
+/// `ListTables` call before it's handed back out. Without that check a
+/// lambda container that's been frozen for a while would hand back a client
+/// whose TLS session or credentials have gone stale, surfacing as a 500 on
+/// the next real request instead of a quiet reconnect.
+pub struct DynamoClientManager {
+    region: Region,
+    verify_on_recycle: bool,
+}
+
+impl DynamoClientManager {
+    pub fn new(region: Region, verify_on_recycle: bool) -> Self {
+        Self {
+            region,
+            verify_on_recycle,
+        }
+    }
+}
+
+#[async_trait]
+impl managed::Manager for DynamoClientManager {
+    type Type = RetryingDynamoDb<DynamoDbClient>;
+    type Error = Error;
+
+    async fn create(&self) -> Result<Self::Type, Self::Error> {
+        Ok(DynamoDbClient::new(self.region.clone()).with_retries(Policy::default()))
+    }
+
+    async fn recycle(&self, client: &mut Self::Type, _metrics: &Metrics) -> RecycleResult<Self::Error> {
+        if !self.verify_on_recycle {
+            return Ok(());
+        }
+
+        client
+            .list_tables(ListTablesInput::default())
+            .await
+            .map(|_| ())
+            .map_err(|e| RecycleError::Backend(e.to_string().into()))
+    }
+}
+
+pub type DynamoPool = managed::Pool<DynamoClientManager>;
+
+/// Pool size and recycle policy are driven by env vars alongside the
+/// existing `ENV` live/local switch: `DYNAMO_POOL_SIZE` caps the number of
+/// concurrent clients (default 10), and `DYNAMO_POOL_RECYCLE=verify`
+/// (the default) health-checks a client before reuse; any other value skips
+/// the check and trusts the client as-is.
+pub fn create_pool() -> Result<DynamoPool, Error> {
+    let env = env::var("ENV").unwrap_or_default();
+
+    let region = if env == "live" {
+        Region::EuWest2
+    } else {
+        Region::Custom {
+            name: "us-east-1".into(),
+            endpoint: "http://localhost:8000".into(),
+        }
+    };
+
+    let size: usize = env::var("DYNAMO_POOL_SIZE")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(10);
+
+    let verify_on_recycle = env::var("DYNAMO_POOL_RECYCLE").unwrap_or_else(|_| "verify".into()) == "verify";
+
+    let manager = DynamoClientManager::new(region, verify_on_recycle);
+
+    managed::Pool::builder(manager)
+        .max_size(size)
+        .build()
+        .map_err(|e| e.to_string().into())
+}
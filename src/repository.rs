@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use dynomite::{
+    dynamodb::{DeleteItemInput, DynamoDb, GetItemInput, PutItemInput, ScanInput},
+    AttributeValue, FromAttributes,
+};
+
+use crate::pool::DynamoPool;
+use crate::{BookEntity, Error};
+
+const BOOKS_TABLE: &str = "books";
+
+fn id_key(id: &str) -> HashMap<String, AttributeValue> {
+    let mut key = HashMap::new();
+    key.insert(
+        "id".to_string(),
+        AttributeValue {
+            s: Some(id.to_string()),
+            ..AttributeValue::default()
+        },
+    );
+    key
+}
+
+/// CRUD access to the `books` table, mocked with `mockall` in unit tests so
+/// handler logic can be asserted without a live `dynamodb-local`.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait BookRepository: Send + Sync {
+    async fn get_book(&self, id: &str) -> Result<Option<BookEntity>, Error>;
+    async fn put_book(&self, book: &BookEntity) -> Result<(), Error>;
+    async fn delete_book(&self, id: &str) -> Result<(), Error>;
+    async fn list_books(&self) -> Result<Vec<BookEntity>, Error>;
+}
+
+/// Borrows a `RetryingDynamoDb` client from the pool for a single request,
+/// rather than holding one client for the repository's whole lifetime, so
+/// cold or unhealthy clients get recycled instead of reused indefinitely.
+pub struct DynamoBookRepository {
+    pool: DynamoPool,
+}
+
+impl DynamoBookRepository {
+    pub fn new(pool: DynamoPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl BookRepository for DynamoBookRepository {
+    async fn get_book(&self, id: &str) -> Result<Option<BookEntity>, Error> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let output = client
+            .get_item(GetItemInput {
+                table_name: BOOKS_TABLE.to_string(),
+                key: id_key(id),
+                ..GetItemInput::default()
+            })
+            .await?;
+
+        match output.item {
+            Some(item) => Ok(Some(BookEntity::from_attrs(item)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn put_book(&self, book: &BookEntity) -> Result<(), Error> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        client
+            .put_item(PutItemInput {
+                table_name: BOOKS_TABLE.to_string(),
+                item: book.clone().into(),
+                ..PutItemInput::default()
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_book(&self, id: &str) -> Result<(), Error> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        client
+            .delete_item(DeleteItemInput {
+                table_name: BOOKS_TABLE.to_string(),
+                key: id_key(id),
+                ..DeleteItemInput::default()
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn list_books(&self) -> Result<Vec<BookEntity>, Error> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+        let output = client
+            .scan(ScanInput {
+                table_name: BOOKS_TABLE.to_string(),
+                ..ScanInput::default()
+            })
+            .await?;
+
+        output
+            .items
+            .unwrap_or_default()
+            .into_iter()
+            .map(|item| BookEntity::from_attrs(item).map_err(Error::from))
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "integration"))]
+mod integration_tests {
+    use super::*;
+    use crate::pool::create_pool;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn round_trips_a_book_through_dynamodb_local() {
+        let repo = DynamoBookRepository::new(create_pool().unwrap());
+
+        let book = BookEntity {
+            id: Uuid::new_v4(),
+            title: "rust".into(),
+            cover_key: None,
+        };
+
+        repo.put_book(&book).await.unwrap();
+
+        let fetched = repo.get_book(&book.id.to_string()).await.unwrap();
+        assert_eq!(fetched.map(|b| b.title), Some("rust".to_string()));
+
+        repo.delete_book(&book.id.to_string()).await.unwrap();
+        assert!(repo.get_book(&book.id.to_string()).await.unwrap().is_none());
+    }
+}